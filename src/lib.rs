@@ -1,137 +1,675 @@
 #![feature(try_trait)]
 
 use std::{
+    collections::HashMap,
     fmt::{Display, Formatter},
     num::ParseIntError,
     option::NoneError,
 };
 
 #[derive(Debug, Clone, Eq, PartialEq)]
-enum Type {
+pub enum Type {
     Whitespace,
     Integer,
+    Identifier,
     Minus,
     Plus,
     Mul,
     Div,
+    Mod,
+    Power,
+    FloorDiv,
+    BitAnd,
+    BitOr,
+    Bang,
+    Eq,
+    NotEq,
+    Lt,
+    Gt,
+    Le,
+    Ge,
+    LParen,
+    RParen,
+    Assign,
+    Semicolon,
     Eof,
 }
 
 struct Token {
     _type: Type,
     value: Option<String>,
+    offset: usize,
+    len: usize,
 }
 
 impl Token {
-    fn is_ops(&self) -> bool {
-        match self._type {
-            Type::Minus | Type::Plus | Type::Mul | Type::Div => true,
-            _ => false,
+    fn is_eof(&self) -> bool {
+        self._type == Type::Eof
+    }
+}
+
+/// A parsed expression tree, produced by [`Interpreter::parse`] and consumed
+/// by [`eval`].
+#[derive(Debug)]
+pub enum Expr {
+    Num(i64),
+    Var(String),
+    Assign {
+        name: String,
+        value: Box<Expr>,
+    },
+    BinOp {
+        op: Type,
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+    },
+}
+
+/// The result of evaluating an [`Expr`]: either an integer or a boolean
+/// produced by a comparison.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Value {
+    Int(i64),
+    Bool(bool),
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+        match self {
+            Value::Int(value) => write!(f, "{}", value),
+            Value::Bool(value) => write!(f, "{}", value),
         }
     }
+}
 
-    fn is_eof(&self) -> bool {
-        self._type == Type::Eof
+/// Variable bindings that persist across successive `Interpreter::eval` calls.
+struct Environment {
+    variables: HashMap<String, Value>,
+}
+
+impl Environment {
+    fn new() -> Self {
+        Environment {
+            variables: HashMap::new(),
+        }
+    }
+
+    fn get(&self, name: &str) -> Option<Value> {
+        self.variables.get(name).cloned()
+    }
+
+    fn set(&mut self, name: String, value: Value) {
+        self.variables.insert(name, value);
     }
 }
 
-pub struct Interpreter {}
+pub struct Interpreter {
+    env: Environment,
+}
 
 impl Interpreter {
-    pub fn evaluate(text: impl ToString) -> Result<Option<i64>, Error> {
-        let stream = Self::tokenize(text.to_string());
-        if let Some(first) = stream.first() {
-            if first.is_eof() {
-                return Ok(None);
+    pub fn new() -> Self {
+        Interpreter {
+            env: Environment::new(),
+        }
+    }
+
+    /// Evaluates a sequence of statements separated by `;` or newline,
+    /// keeping the environment alive across calls, and returns the value of
+    /// the last evaluated statement.
+    pub fn eval(&mut self, text: impl ToString) -> Result<Option<Value>, Error> {
+        let tokens = Self::tokenize(text.to_string())?;
+        if tokens.iter().all(Token::is_eof) {
+            return Ok(None);
+        }
+
+        let mut parser = Parser::new(&tokens);
+        let mut result = None;
+
+        loop {
+            while !parser.at_end() {
+                match parser.current_type() {
+                    Type::Eof | Type::Semicolon => parser.advance(),
+                    _ => break,
+                };
             }
 
-            if first.is_ops() {
-                return Err(Error::with_message("Evaluated text must starts with integer!"));
+            if parser.at_end() {
+                break;
             }
 
-            let mut first = parse_number(first)?;
-            let mut stream = stream.iter().skip(1);
-            while let Some(op) = stream.next() {
-                if op._type == Type::Eof {
-                    break;
-                }
+            let stmt = parser.statement()?;
+            result = Some(eval(&stmt, &mut self.env)?);
 
-                if !op.is_ops() {
-                    return Err(Error::with_message("Item must be operand!"));
-                }
+            match parser.current_type() {
+                Type::Eof | Type::Semicolon => {}
+                _ => return Err(Error::with_message("unexpected token")),
+            }
+        }
 
-                let second = match stream.next() {
-                    Some(token) => token,
-                    None => return Err(Error::with_message("Unexpected end of stream")),
-                };
-                let second = parse_number(&second)?;
-
-                match &op._type {
-                    Type::Plus => first += second,
-                    Type::Minus => first -= second,
-                    Type::Mul => first *= second,
-                    Type::Div => {
-                        if second == 0 {
-                            return Err(Error::with_message("Division by zero!"));
-                        }
+        Ok(result)
+    }
 
-                        first /= second
-                    }
-                    _ => unreachable!(),
-                }
-            }
+    /// Parses a single statement, following the grammar:
+    ///
+    /// ```text
+    /// statement  := Identifier Assign bitwise | bitwise
+    /// bitwise    := comparison ((BitAnd | BitOr) comparison)*
+    /// comparison := expr ((Eq | NotEq | Lt | Gt | Le | Ge) expr)*
+    /// expr       := term ((Plus | Minus) term)*
+    /// term      := power ((Mul | Div | Mod | FloorDiv) power)*
+    /// power     := factor (Power power)?
+    /// factor    := Integer | Identifier | LParen bitwise RParen
+    /// ```
+    ///
+    /// `Token` is an internal type, so this tokenizes `text` itself rather
+    /// than accepting a token slice, keeping the returned [`Expr`] tree
+    /// actually reachable by callers outside the crate.
+    pub fn parse(text: impl ToString) -> Result<Expr, Error> {
+        let tokens = Self::tokenize(text.to_string())?;
+        let mut parser = Parser::new(&tokens);
+        let node = parser.statement()?;
 
-            Ok(Some(first))
-        } else {
-            Ok(None)
+        match parser.current_type() {
+            Type::Eof | Type::Semicolon => Ok(node),
+            _ => Err(Error::with_message("unexpected token")),
         }
     }
 
-    fn tokenize(text: String) -> Vec<Token> {
+    fn tokenize(text: String) -> Result<Vec<Token>, Error> {
         let mut tokens = vec![];
-        let mut chars = text.chars().into_iter().peekable();
+        let mut chars = text.chars().enumerate().peekable();
 
-        while chars.peek() != None {
+        while chars.peek().is_some() {
             let mut val = String::new();
-            while let Some(curr) = chars.next() {
-                let curr_type = detect_char_type(&curr);
+            let mut offset = 0;
+            while let Some((idx, curr)) = chars.next() {
+                let curr_type = detect_char_type(&curr)
+                    .map_err(|_| Error::at_position(idx, format!("unexpected character '{}'", curr)))?;
                 if curr_type == Type::Whitespace {
                     continue;
                 }
 
+                if val.is_empty() {
+                    offset = idx;
+                }
                 val.push(curr);
-                if chars
-                    .peek()
-                    .map(detect_char_type)
-                    .filter(|next_type| next_type == &curr_type)
-                    .is_none()
-                {
-                    let token = Token {
+
+                if curr_type == Type::LParen || curr_type == Type::RParen {
+                    let len = val.len();
+                    tokens.push(Token {
                         _type: curr_type,
                         value: Some(val),
+                        offset,
+                        len,
+                    });
+
+                    break;
+                }
+
+                if curr_type == Type::Integer {
+                    read_number_literal(curr, &mut val, &mut chars);
+
+                    let len = val.len();
+                    tokens.push(Token {
+                        _type: curr_type,
+                        value: Some(val),
+                        offset,
+                        len,
+                    });
+
+                    break;
+                }
+
+                if curr_type == Type::Mul || curr_type == Type::Div {
+                    if chars.peek().map(|&(_, next)| next) == Some(curr) {
+                        let (_, next) = chars.next().unwrap();
+                        val.push(next);
+                    }
+
+                    let resolved_type = match (&curr_type, val.as_str()) {
+                        (Type::Mul, "**") => Type::Power,
+                        (Type::Div, "//") => Type::FloorDiv,
+                        _ => curr_type,
+                    };
+
+                    let len = val.len();
+                    tokens.push(Token {
+                        _type: resolved_type,
+                        value: Some(val),
+                        offset,
+                        len,
+                    });
+
+                    break;
+                }
+
+                if curr_type == Type::Bang {
+                    match chars.peek() {
+                        Some(&(_, '=')) => {
+                            let (_, eq) = chars.next().unwrap();
+                            val.push(eq);
+                        }
+                        _ => return Err(Error::at_position(offset, format!("unexpected character '{}'", curr))),
+                    }
+
+                    let len = val.len();
+                    tokens.push(Token {
+                        _type: Type::NotEq,
+                        value: Some(val),
+                        offset,
+                        len,
+                    });
+
+                    break;
+                }
+
+                if curr_type == Type::Assign || curr_type == Type::Lt || curr_type == Type::Gt {
+                    if chars.peek().map(|&(_, next)| next) == Some('=') {
+                        let (_, eq) = chars.next().unwrap();
+                        val.push(eq);
+                    }
+
+                    let resolved_type = match (&curr_type, val.as_str()) {
+                        (Type::Assign, "==") => Type::Eq,
+                        (Type::Lt, "<=") => Type::Le,
+                        (Type::Gt, ">=") => Type::Ge,
+                        _ => curr_type,
                     };
-                    tokens.push(token);
+
+                    let len = val.len();
+                    tokens.push(Token {
+                        _type: resolved_type,
+                        value: Some(val),
+                        offset,
+                        len,
+                    });
 
                     break;
                 }
+
+                if curr_type == Type::Identifier {
+                    while let Some(&(_, next_char)) = chars.peek() {
+                        if !is_identifier_continue(next_char) {
+                            break;
+                        }
+
+                        chars.next();
+                        val.push(next_char);
+                    }
+                }
+
+                // Every remaining single-char operator (Plus, Minus, Mod,
+                // Power, BitAnd, BitOr, Semicolon, ...) stands on its own;
+                // any operator that legitimately doubles up (`**`, `//`,
+                // `<=`, ...) is already recognized by a dedicated branch
+                // above, so repeating a char here must not be merged into
+                // one token (`1++2` is two `Plus` tokens, not one).
+                let len = val.len();
+                let token = Token {
+                    _type: curr_type,
+                    value: Some(val),
+                    offset,
+                    len,
+                };
+                tokens.push(token);
+
+                break;
+            }
+        }
+
+        Ok(tokens)
+    }
+}
+
+/// Consumes the rest of a numeric literal into `val`, recognizing a leading
+/// `0x`/`0b` radix prefix and `_` digit grouping.
+fn read_number_literal(
+    first: char,
+    val: &mut String,
+    chars: &mut std::iter::Peekable<std::iter::Enumerate<std::str::Chars>>,
+) {
+    let mut is_hex = false;
+    let mut is_bin = false;
+
+    loop {
+        let next = match chars.peek() {
+            Some(&(_, c)) => c,
+            None => break,
+        };
+
+        let consume = if val.len() == 1 && first == '0' && (next == 'x' || next == 'X') {
+            is_hex = true;
+            true
+        } else if val.len() == 1 && first == '0' && (next == 'b' || next == 'B') {
+            is_bin = true;
+            true
+        } else if is_hex {
+            next.is_ascii_hexdigit() || next == '_'
+        } else if is_bin {
+            next == '0' || next == '1' || next == '_'
+        } else {
+            next.is_ascii_digit() || next == '_'
+        };
+
+        if !consume {
+            break;
+        }
+
+        let (_, c) = chars.next().unwrap();
+        val.push(c);
+    }
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(tokens: &'a [Token]) -> Self {
+        Parser { tokens, pos: 0 }
+    }
+
+    fn type_at(&self, offset: usize) -> Type {
+        self.tokens
+            .get(self.pos + offset)
+            .map(|token| token._type.clone())
+            .unwrap_or(Type::Eof)
+    }
+
+    fn current_type(&self) -> Type {
+        self.type_at(0)
+    }
+
+    fn at_end(&self) -> bool {
+        self.pos >= self.tokens.len()
+    }
+
+    fn advance(&mut self) -> Option<&'a Token> {
+        let token = self.tokens.get(self.pos);
+        self.pos += 1;
+
+        token
+    }
+
+    fn statement(&mut self) -> Result<Expr, Error> {
+        if self.current_type() == Type::Identifier && self.type_at(1) == Type::Assign {
+            let name = self
+                .advance()
+                .and_then(|token| token.value.clone())
+                .ok_or_else(|| Error::with_message("unexpected token"))?;
+            self.advance();
+
+            let value = self.bitwise()?;
+
+            Ok(Expr::Assign {
+                name,
+                value: Box::new(value),
+            })
+        } else {
+            self.bitwise()
+        }
+    }
+
+    fn bitwise(&mut self) -> Result<Expr, Error> {
+        let mut node = self.comparison()?;
+
+        loop {
+            match self.current_type() {
+                op @ Type::BitAnd | op @ Type::BitOr => {
+                    self.advance();
+                    let rhs = self.comparison()?;
+                    node = Expr::BinOp {
+                        op,
+                        lhs: Box::new(node),
+                        rhs: Box::new(rhs),
+                    };
+                }
+                _ => break,
+            }
+        }
+
+        Ok(node)
+    }
+
+    fn comparison(&mut self) -> Result<Expr, Error> {
+        let mut node = self.expr()?;
+
+        loop {
+            match self.current_type() {
+                op @ Type::Eq
+                | op @ Type::NotEq
+                | op @ Type::Lt
+                | op @ Type::Gt
+                | op @ Type::Le
+                | op @ Type::Ge => {
+                    self.advance();
+                    let rhs = self.expr()?;
+                    node = Expr::BinOp {
+                        op,
+                        lhs: Box::new(node),
+                        rhs: Box::new(rhs),
+                    };
+                }
+                _ => break,
+            }
+        }
+
+        Ok(node)
+    }
+
+    fn expr(&mut self) -> Result<Expr, Error> {
+        let mut node = self.term()?;
+
+        loop {
+            match self.current_type() {
+                op @ Type::Plus | op @ Type::Minus => {
+                    self.advance();
+                    let rhs = self.term()?;
+                    node = Expr::BinOp {
+                        op,
+                        lhs: Box::new(node),
+                        rhs: Box::new(rhs),
+                    };
+                }
+                _ => break,
+            }
+        }
+
+        Ok(node)
+    }
+
+    fn term(&mut self) -> Result<Expr, Error> {
+        let mut node = self.power()?;
+
+        loop {
+            match self.current_type() {
+                op @ Type::Mul | op @ Type::Div | op @ Type::Mod | op @ Type::FloorDiv => {
+                    self.advance();
+                    let rhs = self.power()?;
+                    node = Expr::BinOp {
+                        op,
+                        lhs: Box::new(node),
+                        rhs: Box::new(rhs),
+                    };
+                }
+                _ => break,
+            }
+        }
+
+        Ok(node)
+    }
+
+    /// `Power` is right-associative, so the right-hand side recurses back
+    /// into `power` instead of stopping at `factor`.
+    fn power(&mut self) -> Result<Expr, Error> {
+        let node = self.factor()?;
+
+        if self.current_type() == Type::Power {
+            self.advance();
+            let rhs = self.power()?;
+
+            return Ok(Expr::BinOp {
+                op: Type::Power,
+                lhs: Box::new(node),
+                rhs: Box::new(rhs),
+            });
+        }
+
+        Ok(node)
+    }
+
+    fn factor(&mut self) -> Result<Expr, Error> {
+        match self.advance() {
+            Some(token) if token._type == Type::Integer => Ok(Expr::Num(parse_number(token)?)),
+            Some(token) if token._type == Type::Identifier => {
+                Ok(Expr::Var(token.value.clone().unwrap_or_default()))
+            }
+            Some(token) if token._type == Type::LParen => {
+                let node = self.bitwise()?;
+
+                match self.advance() {
+                    Some(token) if token._type == Type::RParen => Ok(node),
+                    _ => Err(Error::with_message("unbalanced parentheses")),
+                }
+            }
+            _ => Err(Error::with_message("unexpected token")),
+        }
+    }
+}
+
+fn eval(expr: &Expr, env: &mut Environment) -> Result<Value, Error> {
+    match expr {
+        Expr::Num(value) => Ok(Value::Int(*value)),
+        Expr::Var(name) => env
+            .get(name)
+            .ok_or_else(|| Error::with_message(format!("undefined variable '{}'", name))),
+        Expr::Assign { name, value } => {
+            let value = eval(value, env)?;
+            env.set(name.clone(), value);
+
+            Ok(value)
+        }
+        Expr::BinOp { op, lhs, rhs } => {
+            let lhs = eval(lhs, env)?;
+            let rhs = eval(rhs, env)?;
+
+            match op {
+                Type::Plus => {
+                    let (lhs, rhs) = as_ints("add", lhs, rhs)?;
+                    Ok(Value::Int(lhs + rhs))
+                }
+                Type::Minus => {
+                    let (lhs, rhs) = as_ints("subtract", lhs, rhs)?;
+                    Ok(Value::Int(lhs - rhs))
+                }
+                Type::Mul => {
+                    let (lhs, rhs) = as_ints("multiply", lhs, rhs)?;
+                    Ok(Value::Int(lhs * rhs))
+                }
+                Type::Div => {
+                    let (lhs, rhs) = as_ints("divide", lhs, rhs)?;
+                    if rhs == 0 {
+                        return Err(Error::with_message("Division by zero!"));
+                    }
+
+                    Ok(Value::Int(lhs / rhs))
+                }
+                Type::Mod => {
+                    let (lhs, rhs) = as_ints("take the remainder of", lhs, rhs)?;
+                    if rhs == 0 {
+                        return Err(Error::with_message("Division by zero!"));
+                    }
+
+                    Ok(Value::Int(lhs % rhs))
+                }
+                Type::FloorDiv => {
+                    let (lhs, rhs) = as_ints("floor-divide", lhs, rhs)?;
+                    if rhs == 0 {
+                        return Err(Error::with_message("Division by zero!"));
+                    }
+
+                    Ok(Value::Int(floor_div(lhs, rhs)))
+                }
+                Type::Power => {
+                    let (lhs, rhs) = as_ints("raise", lhs, rhs)?;
+                    if rhs < 0 {
+                        return Err(Error::with_message("negative exponent"));
+                    }
+
+                    Ok(Value::Int(lhs.pow(rhs as u32)))
+                }
+                Type::BitAnd => {
+                    let (lhs, rhs) = as_ints("bitwise-and", lhs, rhs)?;
+                    Ok(Value::Int(lhs & rhs))
+                }
+                Type::BitOr => {
+                    let (lhs, rhs) = as_ints("bitwise-or", lhs, rhs)?;
+                    Ok(Value::Int(lhs | rhs))
+                }
+                Type::Eq => Ok(Value::Bool(lhs == rhs)),
+                Type::NotEq => Ok(Value::Bool(lhs != rhs)),
+                Type::Lt => {
+                    let (lhs, rhs) = as_ints("compare", lhs, rhs)?;
+                    Ok(Value::Bool(lhs < rhs))
+                }
+                Type::Gt => {
+                    let (lhs, rhs) = as_ints("compare", lhs, rhs)?;
+                    Ok(Value::Bool(lhs > rhs))
+                }
+                Type::Le => {
+                    let (lhs, rhs) = as_ints("compare", lhs, rhs)?;
+                    Ok(Value::Bool(lhs <= rhs))
+                }
+                Type::Ge => {
+                    let (lhs, rhs) = as_ints("compare", lhs, rhs)?;
+                    Ok(Value::Bool(lhs >= rhs))
+                }
+                _ => unreachable!(),
             }
         }
+    }
+}
+
+/// Unwraps both operands of an arithmetic operator as integers, rejecting
+/// booleans with a typed error naming the operator (`"cannot add
+/// booleans"`, `"cannot compare booleans"`, ...).
+fn as_ints(op: &str, lhs: Value, rhs: Value) -> Result<(i64, i64), Error> {
+    match (lhs, rhs) {
+        (Value::Int(lhs), Value::Int(rhs)) => Ok((lhs, rhs)),
+        _ => Err(Error::with_message(format!("cannot {} booleans", op))),
+    }
+}
+
+/// Integer division rounded toward negative infinity, as opposed to the
+/// `/` operator's round-toward-zero behavior.
+fn floor_div(lhs: i64, rhs: i64) -> i64 {
+    let quotient = lhs / rhs;
+    let remainder = lhs % rhs;
 
-        tokens
+    if remainder != 0 && (remainder < 0) != (rhs < 0) {
+        quotient - 1
+    } else {
+        quotient
     }
 }
 
 #[cfg(test)]
 mod evaluate_tests {
-    use super::Interpreter;
+    use super::{Interpreter, Value};
+
+    fn evaluate(text: &str) -> Result<Option<Value>, super::Error> {
+        Interpreter::new().eval(text)
+    }
 
     macro_rules! add_test {
         ($($name:ident: $eval:expr, result: $res:expr,)+) => {
             $(
                 #[test]
                 fn $name() {
-                    let result = Interpreter::evaluate($eval).unwrap().unwrap();
-                    assert_eq!(result, $res);
+                    let result = evaluate($eval).unwrap().unwrap();
+                    assert_eq!(result, Value::Int($res));
                 }
             )+
         };
@@ -151,23 +689,111 @@ mod evaluate_tests {
         eval_9: "1*2\n", result: 2,
         eval_10: "4/2\n", result: 2,
         eval_11: "4 /2 * 5 + 5 - 3", result: 12,
+        eval_precedence: "1+2*3", result: 7,
+        eval_precedence_2: "2*3+1", result: 7,
+        eval_parens: "(1+2)*3", result: 9,
+        eval_nested_parens: "((1+2)*(3+4))", result: 21,
+        eval_hex: "0xff\n", result: 255,
+        eval_hex_upper: "0XFF\n", result: 255,
+        eval_bin: "0b1010\n", result: 10,
+        eval_underscores: "1_000_000\n", result: 1000000,
+        eval_hex_underscores: "0xFF_FF\n", result: 65535,
+        eval_mod: "7%3", result: 1,
+        eval_floor_div: "7//2", result: 3,
+        eval_floor_div_negative: "(0-7)//2", result: -4,
+        eval_power_caret: "2^10", result: 1024,
+        eval_power_stars: "2**10", result: 1024,
+        eval_power_right_assoc: "2^3^2", result: 512,
+        eval_bitand: "6&3", result: 2,
+        eval_bitor: "6|1", result: 7,
+        eval_precedence_power_over_mul: "2*3^2", result: 18,
+        eval_precedence_mul_over_add: "1+2%3", result: 3,
+        eval_precedence_add_over_bitwise: "1|2+3", result: 5,
+    );
+
+    macro_rules! add_bool_test {
+        ($($name:ident: $eval:expr, result: $res:expr,)+) => {
+            $(
+                #[test]
+                fn $name() {
+                    let result = evaluate($eval).unwrap().unwrap();
+                    assert_eq!(result, Value::Bool($res));
+                }
+            )+
+        };
+    }
+
+    add_bool_test!(
+        eval_eq: "1==1", result: true,
+        eval_eq_false: "1==2", result: false,
+        eval_not_eq: "1!=2", result: true,
+        eval_lt: "1<2", result: true,
+        eval_gt: "2>1", result: true,
+        eval_le: "2<=2", result: true,
+        eval_ge: "2>=2", result: true,
+        eval_precedence_arith_over_comparison: "1+1==2", result: true,
     );
 
     #[test]
     fn check_division_by_zero() {
-        let result = Interpreter::evaluate("1/0");
+        let result = evaluate("1/0");
+
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn check_modulo_by_zero() {
+        let result = evaluate("1%0");
+
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn check_floor_division_by_zero() {
+        let result = evaluate("1//0");
+
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn check_negative_exponent() {
+        let result = evaluate("2^(1-2)");
+
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn check_identifier_with_trailing_digits() {
+        let result = evaluate("x1 = 5; x1 * 2").unwrap().unwrap();
+
+        assert_eq!(result, Value::Int(10));
+    }
+
+    #[test]
+    fn check_arithmetic_on_booleans_is_an_error() {
+        let result = evaluate("x = 1 < 2; x + 1");
+
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "cannot add booleans"
+        );
+    }
+
+    #[test]
+    fn check_comparison_on_booleans_is_an_error() {
+        let result = evaluate("(1<2) < (2<3)");
 
         assert_eq!(result.is_err(), true);
     }
 
     #[test]
     fn evaluate_empty_string() {
-        let result = Interpreter::evaluate("");
+        let result = evaluate("");
 
         assert_eq!(result.is_ok(), true);
         assert_eq!(result.unwrap().is_none(), true);
 
-        let result = Interpreter::evaluate("\n");
+        let result = evaluate("\n");
 
         assert_eq!(result.is_ok(), true);
         assert_eq!(result.unwrap().is_none(), true);
@@ -178,7 +804,7 @@ mod evaluate_tests {
             $(
                 #[test]
                 fn $name() {
-                    let result = Interpreter::evaluate($eval);
+                    let result = evaluate($eval);
                     assert_eq!(result.is_err(), true);
                 }
             )+
@@ -194,39 +820,133 @@ mod evaluate_tests {
 
     #[test]
     fn check_non_ops_operator() {
-        let result = Interpreter::evaluate("1 1 +");
+        let result = evaluate("1 1 +");
 
         assert_eq!(result.is_err(), true);
     }
 
     #[test]
     fn check_forgotten_second_operand() {
-        let result = Interpreter::evaluate("1+");
+        let result = evaluate("1+");
 
         assert_eq!(result.is_err(), true);
     }
 
     #[test]
     fn check_wrong_first_argument() {
-        let result = Interpreter::evaluate(" +1");
+        let result = evaluate(" +1");
 
         assert_eq!(result.is_err(), true);
     }
 
     #[test]
     fn check_wrong_second_argument() {
-        let result = Interpreter::evaluate("1++");
+        let result = evaluate("1++");
+
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn check_doubled_plus_is_not_a_single_operator() {
+        let result = evaluate("1++2");
+
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn check_doubled_bitand_is_not_a_single_operator() {
+        let result = evaluate("6&&3");
+
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn check_unbalanced_parentheses() {
+        let result = evaluate("(1+2");
+
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn check_unexpected_closing_paren() {
+        let result = evaluate("1+2)");
+
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn check_invalid_character_reports_position() {
+        let result = evaluate("1 + @ 3");
+
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "error at column 5: unexpected character '@'"
+        );
+    }
+
+    #[test]
+    fn check_hex_literal_missing_digits() {
+        let result = evaluate("0xgg");
+
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn assignment_evaluates_to_assigned_value() {
+        let result = evaluate("x = 5").unwrap().unwrap();
+
+        assert_eq!(result, Value::Int(5));
+    }
+
+    #[test]
+    fn variables_persist_across_eval_calls() {
+        let mut interpreter = Interpreter::new();
+
+        let assigned = interpreter.eval("x = 5\n").unwrap().unwrap();
+        assert_eq!(assigned, Value::Int(5));
+
+        let result = interpreter.eval("x * 2\n").unwrap().unwrap();
+        assert_eq!(result, Value::Int(10));
+    }
+
+    #[test]
+    fn statements_separated_by_semicolon() {
+        let result = evaluate("x = 5; x * 2").unwrap().unwrap();
+
+        assert_eq!(result, Value::Int(10));
+    }
+
+    #[test]
+    fn statements_separated_by_newline() {
+        let result = evaluate("x = 5\nx * 2").unwrap().unwrap();
+
+        assert_eq!(result, Value::Int(10));
+    }
+
+    #[test]
+    fn referencing_undefined_variable_is_an_error() {
+        let result = evaluate("y + 1");
 
         assert_eq!(result.is_err(), true);
     }
 }
 
 fn parse_number(token: &Token) -> Result<i64, Error> {
-    if token.is_ops() || token._type == Type::Eof || token._type == Type::Whitespace {
+    if token._type != Type::Integer {
         return Err(Error::with_message("Evaluated text must be integer!"));
     }
 
-    token.value.clone()?.parse().map_err(Into::into)
+    let literal = token.value.clone()?.replace('_', "");
+
+    let (radix, digits) = if literal.starts_with("0x") || literal.starts_with("0X") {
+        (16, &literal[2..])
+    } else if literal.starts_with("0b") || literal.starts_with("0B") {
+        (2, &literal[2..])
+    } else {
+        (10, literal.as_str())
+    };
+
+    i64::from_str_radix(digits, radix).map_err(Into::into)
 }
 
 #[cfg(test)]
@@ -238,6 +958,8 @@ mod parse_number_tests {
         let token = Token {
             _type: Type::Integer,
             value: Some("0123456789".into()),
+            offset: 0,
+            len: 10,
         };
         let value = parse_number(&token);
 
@@ -250,7 +972,7 @@ mod parse_number_tests {
             $(
                 #[test]
                 fn $name() {
-                    let token = Token { _type: $type, value: None };
+                    let token = Token { _type: $type, value: None, offset: 0, len: 0 };
                     let err = parse_number(&token);
                     assert_eq!(err.is_err(), true);
                 }
@@ -263,6 +985,23 @@ mod parse_number_tests {
         { name: parse_sub, type: Type::Minus },
         { name: parse_mul, type: Type::Mul },
         { name: parse_div, type: Type::Div },
+        { name: parse_mod, type: Type::Mod },
+        { name: parse_power, type: Type::Power },
+        { name: parse_floor_div, type: Type::FloorDiv },
+        { name: parse_bitand, type: Type::BitAnd },
+        { name: parse_bitor, type: Type::BitOr },
+        { name: parse_bang, type: Type::Bang },
+        { name: parse_eq, type: Type::Eq },
+        { name: parse_not_eq, type: Type::NotEq },
+        { name: parse_lt, type: Type::Lt },
+        { name: parse_gt, type: Type::Gt },
+        { name: parse_le, type: Type::Le },
+        { name: parse_ge, type: Type::Ge },
+        { name: parse_lparen, type: Type::LParen },
+        { name: parse_rparen, type: Type::RParen },
+        { name: parse_assign, type: Type::Assign },
+        { name: parse_semicolon, type: Type::Semicolon },
+        { name: parse_identifier, type: Type::Identifier },
         { name: parse_eof, type: Type::Eof },
         { name: parse_whitespace, type: Type::Whitespace },
     );
@@ -272,6 +1011,8 @@ mod parse_number_tests {
         let token = Token {
             _type: Type::Integer,
             value: None,
+            offset: 0,
+            len: 0,
         };
         let value = parse_number(&token);
 
@@ -283,26 +1024,90 @@ mod parse_number_tests {
         let token = Token {
             _type: Type::Integer,
             value: Some("abc".into()),
+            offset: 0,
+            len: 3,
         };
         let value = parse_number(&token);
 
         assert_eq!(value.is_err(), true);
     }
+
+    #[test]
+    fn parsing_hex_number() {
+        let token = Token {
+            _type: Type::Integer,
+            value: Some("0xff".into()),
+            offset: 0,
+            len: 4,
+        };
+        let value = parse_number(&token);
+
+        assert_eq!(value.is_ok(), true);
+        assert_eq!(value.ok(), Some(255));
+    }
+
+    #[test]
+    fn parsing_binary_number() {
+        let token = Token {
+            _type: Type::Integer,
+            value: Some("0b1010".into()),
+            offset: 0,
+            len: 6,
+        };
+        let value = parse_number(&token);
+
+        assert_eq!(value.is_ok(), true);
+        assert_eq!(value.ok(), Some(10));
+    }
+
+    #[test]
+    fn parsing_number_with_underscores() {
+        let token = Token {
+            _type: Type::Integer,
+            value: Some("1_000_000".into()),
+            offset: 0,
+            len: 9,
+        };
+        let value = parse_number(&token);
+
+        assert_eq!(value.is_ok(), true);
+        assert_eq!(value.ok(), Some(1_000_000));
+    }
 }
 
-fn detect_char_type(item: &char) -> Type {
+fn detect_char_type(item: &char) -> Result<Type, Error> {
     match item {
-        '0'...'9' => Type::Integer,
-        ' ' => Type::Whitespace,
-        '-' => Type::Minus,
-        '+' => Type::Plus,
-        '*' => Type::Mul,
-        '/' => Type::Div,
-        '\n' => Type::Eof,
-        _ => panic!("Parse error!"),
+        '0'...'9' => Ok(Type::Integer),
+        ' ' => Ok(Type::Whitespace),
+        '-' => Ok(Type::Minus),
+        '+' => Ok(Type::Plus),
+        '*' => Ok(Type::Mul),
+        '/' => Ok(Type::Div),
+        '%' => Ok(Type::Mod),
+        '^' => Ok(Type::Power),
+        '&' => Ok(Type::BitAnd),
+        '|' => Ok(Type::BitOr),
+        '!' => Ok(Type::Bang),
+        '<' => Ok(Type::Lt),
+        '>' => Ok(Type::Gt),
+        '(' => Ok(Type::LParen),
+        ')' => Ok(Type::RParen),
+        '=' => Ok(Type::Assign),
+        ';' => Ok(Type::Semicolon),
+        '\n' => Ok(Type::Eof),
+        c if is_identifier_start(*c) => Ok(Type::Identifier),
+        other => Err(Error::with_message(format!("unexpected character '{}'", other))),
     }
 }
 
+fn is_identifier_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_'
+}
+
+fn is_identifier_continue(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
 #[cfg(test)]
 mod detect_char_type_tests {
     use super::{detect_char_type, Type};
@@ -312,7 +1117,7 @@ mod detect_char_type_tests {
             $(
                 #[test]
                 fn $name() {
-                    let result = detect_char_type(&$item);
+                    let result = detect_char_type(&$item).unwrap();
 
                     assert_eq!(result, $expected);
                 }
@@ -391,6 +1196,71 @@ mod detect_char_type_tests {
             parsed: '/',
             expected_type: Type::Div
         },
+        {
+            name: parse_mod,
+            parsed: '%',
+            expected_type: Type::Mod
+        },
+        {
+            name: parse_power,
+            parsed: '^',
+            expected_type: Type::Power
+        },
+        {
+            name: parse_bitand,
+            parsed: '&',
+            expected_type: Type::BitAnd
+        },
+        {
+            name: parse_bitor,
+            parsed: '|',
+            expected_type: Type::BitOr
+        },
+        {
+            name: parse_bang,
+            parsed: '!',
+            expected_type: Type::Bang
+        },
+        {
+            name: parse_lt,
+            parsed: '<',
+            expected_type: Type::Lt
+        },
+        {
+            name: parse_gt,
+            parsed: '>',
+            expected_type: Type::Gt
+        },
+        {
+            name: parse_lparen,
+            parsed: '(',
+            expected_type: Type::LParen
+        },
+        {
+            name: parse_rparen,
+            parsed: ')',
+            expected_type: Type::RParen
+        },
+        {
+            name: parse_assign,
+            parsed: '=',
+            expected_type: Type::Assign
+        },
+        {
+            name: parse_semicolon,
+            parsed: ';',
+            expected_type: Type::Semicolon
+        },
+        {
+            name: parse_identifier_letter,
+            parsed: 'x',
+            expected_type: Type::Identifier
+        },
+        {
+            name: parse_identifier_underscore,
+            parsed: '_',
+            expected_type: Type::Identifier
+        },
         {
             name: parse_eof,
             parsed: '\n',
@@ -399,9 +1269,10 @@ mod detect_char_type_tests {
     );
 
     #[test]
-    #[should_panic]
     fn parse_wrong_symbol() {
-        detect_char_type(&'a');
+        let result = detect_char_type(&'@');
+
+        assert_eq!(result.is_err(), true);
     }
 }
 
@@ -416,11 +1287,21 @@ impl Error {
 
         Error { repr }
     }
+
+    fn at_position(offset: usize, message: impl ToString) -> Self {
+        let repr = Repr::AtPosition {
+            offset,
+            message: message.to_string(),
+        };
+
+        Error { repr }
+    }
 }
 
 #[derive(Debug)]
 enum Repr {
     Inner(String),
+    AtPosition { offset: usize, message: String },
     NoneError(NoneError),
     Other(Box<std::error::Error>),
 }
@@ -431,6 +1312,9 @@ impl Display for Error {
     fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
         match &self.repr {
             Repr::Inner(message) => write!(f, "{}", message),
+            Repr::AtPosition { offset, message } => {
+                write!(f, "error at column {}: {}", offset + 1, message)
+            }
             Repr::NoneError(_) => write!(f, "Trying to unwrap None"),
             Repr::Other(e) => e.fmt(f),
         }
@@ -1,17 +1,33 @@
-use std::io::Write;
+use rustyline::error::ReadlineError;
+use rustyline::Editor;
+
+const HISTORY_FILE: &str = "history.txt";
 
 fn main() {
-    let stdin = std::io::stdin();
-    let mut stdout = std::io::stdout();
+    let mut interpreter = lsbasi::Interpreter::new();
+    let mut editor = Editor::<()>::new();
+    let _ = editor.load_history(HISTORY_FILE);
+
     loop {
-        print!("calc> ");
-        let _ = stdout.flush();
-        let mut buf = String::new();
-        let _ = stdin.read_line(&mut buf);
-        match lsbasi::Interpreter::evaluate(buf.as_str()) {
-            Ok(Some(result)) => println!("{}", result),
-            Ok(None) => {},
-            Err(e) => eprintln!("{}", e),
+        match editor.readline("calc> ") {
+            Ok(line) => {
+                if !line.trim().is_empty() {
+                    editor.add_history_entry(line.as_str());
+                }
+
+                match interpreter.eval(line.as_str()) {
+                    Ok(Some(result)) => println!("{}", result),
+                    Ok(None) => {}
+                    Err(e) => eprintln!("{}", e),
+                }
+            }
+            Err(ReadlineError::Eof) => break,
+            Err(e) => {
+                eprintln!("{}", e);
+                break;
+            }
         }
     }
+
+    let _ = editor.save_history(HISTORY_FILE);
 }